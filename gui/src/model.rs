@@ -2,7 +2,7 @@ use std::borrow::Cow;
 
 use bytemuck::{Pod, Zeroable};
 use nalgebra_glm as glm;
-use glm::{Vec4, Mat4};
+use glm::{Vec3, Vec4, Mat4};
 use gltf::Gltf;
 use wgpu::util::DeviceExt;
 
@@ -18,78 +18,300 @@ pub struct GPUVertex {
     pub color: [f32; 4],
 }
 
-pub struct Model {
+/// GPU buffers for one glTF primitive, drawn with its own `draw_indexed`
+/// call but sharing `Model`'s pipelines and bind groups. A multi-solid,
+/// multi-material file (everything `step_to_gltf` emits once it has more
+/// than one connected solid, or more than one STEP color in a solid)
+/// produces one `json::Mesh` per solid and one `json::Primitive` per
+/// material, so `Model` needs one of these per primitive to show all of
+/// it rather than just the first.
+struct PrimitiveBuffers {
     vertex_buf: wgpu::Buffer,
     index_buf: wgpu::Buffer,
+    index_count: u32,
+}
+
+/// View/model matrices consumed by bind group 0, written once per frame.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Pod, Zeroable)]
+struct TransformUniform {
+    view: [[f32; 4]; 4],
+    model: [[f32; 4]; 4],
+}
+
+/// Light and shadow parameters consumed by bind group 1. `light_space`
+/// is the same orthographic light view-projection used to render the
+/// shadow map, so the main pass can project each fragment into it.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Pod, Zeroable)]
+struct LightUniform {
+    light_space: [[f32; 4]; 4],
+    light_dir: [f32; 4],
+    light_color: [f32; 4],
+    camera_pos: [f32; 4],
+}
+
+const SHADOW_MAP_SIZE: u32 = 2048;
+const DEFAULT_LIGHT_DIR: [f32; 3] = [-0.4, -1.0, -0.3];
+const DEFAULT_LIGHT_COLOR: [f32; 3] = [1.0, 1.0, 1.0];
+const DEFAULT_BASE_COLOR: [f32; 4] = [0.8, 0.8, 0.8, 1.0];
+
+/// Errors loading a glTF primitive into GPU buffers.
+#[derive(Debug)]
+pub enum ModelError {
+    MissingBlob,
+    UnsupportedBufferSource,
+    NoMesh,
+    NoPrimitive,
+    MissingPositions,
+}
+
+impl std::fmt::Display for ModelError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            ModelError::MissingBlob => write!(f, "glTF buffer has no embedded blob"),
+            ModelError::UnsupportedBufferSource => write!(f, "only GLB/embedded buffers are supported"),
+            ModelError::NoMesh => write!(f, "glTF contains no mesh"),
+            ModelError::NoPrimitive => write!(f, "glTF mesh contains no primitive"),
+            ModelError::MissingPositions => write!(f, "primitive has no POSITION attribute"),
+        }
+    }
+}
+
+impl std::error::Error for ModelError {}
+
+/// Computes per-vertex smooth normals from face normals (cross product of
+/// two triangle edges) when a primitive has none, used so third-party
+/// glTF files without NORMAL attributes don't panic on load.
+fn compute_smooth_normals(positions: &[[f32; 3]], indices: &[u32]) -> Vec<[f32; 3]> {
+    let mut normals = vec![Vec3::zeros(); positions.len()];
+
+    for tri in indices.chunks(3) {
+        if tri.len() < 3 {
+            continue;
+        }
+        let (i0, i1, i2) = (tri[0] as usize, tri[1] as usize, tri[2] as usize);
+        let p0 = Vec3::from(positions[i0]);
+        let p1 = Vec3::from(positions[i1]);
+        let p2 = Vec3::from(positions[i2]);
+        let face_normal = (p1 - p0).cross(&(p2 - p0));
+        normals[i0] += face_normal;
+        normals[i1] += face_normal;
+        normals[i2] += face_normal;
+    }
+
+    normals.iter()
+        .map(|n| {
+            if n.norm() > 1.0e-8 {
+                let n = glm::normalize(n);
+                [n.x, n.y, n.z]
+            } else {
+                [0.0, 0.0, 1.0]
+            }
+        })
+        .collect()
+}
+
+/// Inverse-transpose of a node transform's linear (3x3) part, used to
+/// transform normals correctly under scale/rotation. Translation (all
+/// `step_to_gltf` emits today) leaves this at the identity, but a node
+/// loaded from a third-party glTF file can carry real rotation/scale.
+fn normal_matrix(m: &Mat4) -> nalgebra::Matrix3<f32> {
+    let linear = nalgebra::Matrix3::new(
+        m[(0, 0)], m[(0, 1)], m[(0, 2)],
+        m[(1, 0)], m[(1, 1)], m[(1, 2)],
+        m[(2, 0)], m[(2, 1)], m[(2, 2)],
+    );
+    linear.try_inverse().unwrap_or(linear).transpose()
+}
+
+/// Applies a node's transform to one of its local-space vertices,
+/// returning the world-space position and normal.
+fn transform_vertex(m: &Mat4, n: &nalgebra::Matrix3<f32>, position: [f32; 3], normal: [f32; 3]) -> ([f32; 3], [f32; 3]) {
+    let world_pos = m * glm::vec4(position[0], position[1], position[2], 1.0);
+    let world_normal = glm::normalize(&(n * glm::vec3(normal[0], normal[1], normal[2])));
+    ([world_pos.x, world_pos.y, world_pos.z], [world_normal.x, world_normal.y, world_normal.z])
+}
+
+/// `nalgebra_glm`'s `ortho`/`perspective` follow OpenGL convention and
+/// leave clip-space z in `[-1, 1]`; wgpu expects `[0, 1]`. The main pass's
+/// projection comes from `Camera` and is assumed to already carry this
+/// same remap, so `light_space_matrix` applies it too rather than
+/// shipping a shadow frustum that clips away half its near/far range.
+fn opengl_to_wgpu_matrix() -> Mat4 {
+    Mat4::new(
+        1.0, 0.0, 0.0, 0.0,
+        0.0, 1.0, 0.0, 0.0,
+        0.0, 0.0, 0.5, 0.5,
+        0.0, 0.0, 0.0, 1.0,
+    )
+}
+
+/// Builds the orthographic light-space matrix, sized to enclose the
+/// model's bounding sphere so the whole part lands in the shadow map.
+fn light_space_matrix(min: Vec3, max: Vec3, light_dir: Vec3) -> Mat4 {
+    let center = (min + max) * 0.5;
+    let radius = f32::max((max - min).norm() * 0.5, 0.001);
+    let light_dir = glm::normalize(&light_dir);
+    let up = if light_dir.y.abs() > 0.99 { glm::vec3(0.0, 0.0, 1.0) } else { glm::vec3(0.0, 1.0, 0.0) };
+    let eye = center - light_dir * radius * 2.0;
+    let view = glm::look_at(&eye, &center, &up);
+    let proj = glm::ortho(-radius, radius, -radius, radius, 0.01, radius * 4.0);
+    opengl_to_wgpu_matrix() * proj * view
+}
+
+pub struct Model {
+    primitives: Vec<PrimitiveBuffers>,
     uniform_buf: wgpu::Buffer,
     bind_group: wgpu::BindGroup,
-    index_count: u32,
     render_pipeline: wgpu::RenderPipeline,
+
+    light_uniform_buf: wgpu::Buffer,
+    light_bind_group: wgpu::BindGroup,
+    light_space: Mat4,
+
+    shadow_uniform_buf: wgpu::Buffer,
+    shadow_bind_group: wgpu::BindGroup,
+    shadow_pipeline: wgpu::RenderPipeline,
+    shadow_view: wgpu::TextureView,
+
+    swapchain_format: wgpu::TextureFormat,
+    msaa_sample_count: u32,
+    msaa_color_view: Option<wgpu::TextureView>,
+    depth_view: wgpu::TextureView,
 }
 
 impl Model {
-    pub fn new(device: &wgpu::Device, swapchain_format: wgpu::TextureFormat, gltf: &Gltf
-        ) -> (Self, Vec<GPUVertex>) {
+    pub fn new(device: &wgpu::Device, swapchain_format: wgpu::TextureFormat, gltf: &Gltf,
+               size: (u32, u32), msaa_sample_count: u32,
+        ) -> Result<(Self, Vec<GPUVertex>), ModelError> {
 
         // Load buffers
         let mut buffer_data = Vec::new();
         for buffer in gltf.buffers() {
             let bin = match buffer.source() {
-                gltf::buffer::Source::Bin => {
-                    if let Some(blob) = gltf.blob.clone() {
-                        blob
-                    } else {
-                        panic!("Missing Blob");
-                    }
-                }
-                _ => panic!("Only GLB/embedded buffers supported")
+                gltf::buffer::Source::Bin => gltf.blob.clone().ok_or(ModelError::MissingBlob)?,
+                _ => return Err(ModelError::UnsupportedBufferSource),
             };
 
             buffer_data.push(bin);
         }
 
-        let mesh = gltf.meshes().next().unwrap();
-        let primitive = mesh.primitives().next().unwrap();
+        if gltf.meshes().next().is_none() {
+            return Err(ModelError::NoMesh);
+        }
 
-        let reader = primitive.reader(|buffer| Some(&buffer_data[buffer.index()]));
+        // Walk every node that references a mesh (not just the first mesh's
+        // first primitive) so multi-solid assemblies and multi-material
+        // parts - everything step_to_gltf emits once a file has more than
+        // one connected solid or STEP color - render in full. Node-local
+        // vertices are baked into world space here since Model has no
+        // per-instance uniform to carry a node transform at draw time.
+        let mut primitives_gpu: Vec<PrimitiveBuffers> = Vec::new();
+        let mut vertices: Vec<GPUVertex> = Vec::new();
 
-        let (positions, normals, colors) = (
-            reader.read_positions().unwrap(),
-            reader.read_normals().unwrap(),
-            reader.read_colors(0).unwrap().into_rgba_f32(),
-        );
+        // A node's transform is relative to its parent, so keep every
+        // node's local matrix (`Transform::matrix()` is column-major, as
+        // `make_mat4` expects) and its parent to compose the full chain.
+        let local_transforms: Vec<Mat4> = gltf.nodes()
+            .map(|node| glm::make_mat4(&node.transform().matrix().concat()))
+            .collect();
+        let mut parent_of: Vec<Option<usize>> = vec![None; local_transforms.len()];
+        for node in gltf.nodes() {
+            for child in node.children() {
+                parent_of[child.index()] = Some(node.index());
+            }
+        }
 
-        let indices = reader.read_indices().map(|indices| indices.into_u32());
-        let indices = match indices {
-            Some(indices) => indices.collect::<Vec<_>>(),
-            None => (0..positions.len() as u32).collect(),
-        };
+        for node in gltf.nodes() {
+            let mesh = match node.mesh() {
+                Some(mesh) => mesh,
+                None => continue,
+            };
 
-        let vertices = positions
-            .zip(normals)
-            .zip(colors)
-            .map(|((pos, norm), color)| GPUVertex {
-                pos: [pos[0], pos[1], pos[2], 1.0],
-                norm: [norm[0], norm[1], norm[2], 1.0],
-                color,
-            })
-            .collect::<Vec<_>>();
+            let mut node_transform = local_transforms[node.index()];
+            let mut ancestor = parent_of[node.index()];
+            while let Some(p) = ancestor {
+                node_transform = local_transforms[p] * node_transform;
+                ancestor = parent_of[p];
+            }
+            let node_normal_matrix = normal_matrix(&node_transform);
 
-        let vertex_buf = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
-            label: Some("Vertex Buffer"),
-            contents: bytemuck::cast_slice(&vertices),
-            usage: wgpu::BufferUsage::VERTEX,
-        });
+            for primitive in mesh.primitives() {
+                let reader = primitive.reader(|buffer| Some(&buffer_data[buffer.index()]));
 
-        let index_buf = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
-            label: Some("Index Buffer"),
-            contents: bytemuck::cast_slice(&indices),
-            usage: wgpu::BufferUsage::INDEX,
-        });
+                let local_positions: Vec<[f32; 3]> = reader.read_positions()
+                    .ok_or(ModelError::MissingPositions)?
+                    .collect();
+
+                let indices: Vec<u32> = match reader.read_indices().map(|indices| indices.into_u32()) {
+                    Some(indices) => indices.collect(),
+                    None => (0..local_positions.len() as u32).collect(),
+                };
+
+                // Third-party glTF files aren't guaranteed to carry
+                // NORMAL/COLOR_0 attributes; fall back instead of
+                // panicking on `.unwrap()`.
+                let local_normals: Vec<[f32; 3]> = match reader.read_normals() {
+                    Some(normals) => normals.collect(),
+                    None => compute_smooth_normals(&local_positions, &indices),
+                };
+
+                // `step_to_gltf` now carries STEP presentation color on the
+                // primitive's material (`pbrMetallicRoughness.baseColorFactor`)
+                // rather than as a COLOR_0 vertex attribute, so prefer that;
+                // fall back to COLOR_0 for third-party files that still
+                // vertex-paint, then to a flat default if neither is present.
+                let colors: Vec<[f32; 4]> = if primitive.material().index().is_some() {
+                    vec![primitive.material().pbr_metallic_roughness().base_color_factor(); local_positions.len()]
+                } else {
+                    match reader.read_colors(0) {
+                        Some(colors) => colors.into_rgba_f32().collect(),
+                        None => vec![DEFAULT_BASE_COLOR; local_positions.len()],
+                    }
+                };
+
+                let primitive_vertices = local_positions.iter()
+                    .zip(local_normals.iter())
+                    .zip(colors.iter())
+                    .map(|((pos, norm), color)| {
+                        let (world_pos, world_norm) = transform_vertex(&node_transform, &node_normal_matrix, *pos, *norm);
+                        GPUVertex {
+                            pos: [world_pos[0], world_pos[1], world_pos[2], 1.0],
+                            norm: [world_norm[0], world_norm[1], world_norm[2], 1.0],
+                            color: *color,
+                        }
+                    })
+                    .collect::<Vec<_>>();
+
+                let vertex_buf = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                    label: Some("Vertex Buffer"),
+                    contents: bytemuck::cast_slice(&primitive_vertices),
+                    usage: wgpu::BufferUsage::VERTEX,
+                });
+
+                let index_buf = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                    label: Some("Index Buffer"),
+                    contents: bytemuck::cast_slice(&indices),
+                    usage: wgpu::BufferUsage::INDEX,
+                });
+
+                primitives_gpu.push(PrimitiveBuffers {
+                    vertex_buf,
+                    index_buf,
+                    index_count: indices.len() as u32,
+                });
+                vertices.extend(primitive_vertices);
+            }
+        }
+
+        if primitives_gpu.is_empty() {
+            return Err(ModelError::NoPrimitive);
+        }
 
         let uniform_buf = device.create_buffer(&wgpu::BufferDescriptor {
             label: Some("Uniform Buffer"),
-            size: std::mem::size_of::<Mat4>() as wgpu::BufferAddress * 2,
+            size: std::mem::size_of::<TransformUniform>() as wgpu::BufferAddress,
             usage: wgpu::BufferUsage::UNIFORM | wgpu::BufferUsage::COPY_DST,
             mapped_at_creation: false,
         });
@@ -104,17 +326,162 @@ impl Model {
                         ty: wgpu::BufferBindingType::Uniform,
                         has_dynamic_offset: false,
                         min_binding_size: wgpu::BufferSize::new(
-                            std::mem::size_of::<Mat4>() as u64 * 2),
+                            std::mem::size_of::<TransformUniform>() as u64),
                     },
                     count: None,
                 },
             ],
         });
 
+        // Bounding box of the model, used to size the light's orthographic
+        // projection so the whole part falls inside the shadow map.
+        let (mut bounds_min, mut bounds_max) = (
+            glm::vec3(f32::MAX, f32::MAX, f32::MAX),
+            glm::vec3(f32::MIN, f32::MIN, f32::MIN),
+        );
+        for v in &vertices {
+            for i in 0..3 {
+                bounds_min[i] = bounds_min[i].min(v.pos[i]);
+                bounds_max[i] = bounds_max[i].max(v.pos[i]);
+            }
+        }
+        let light_dir = glm::make_vec3(&DEFAULT_LIGHT_DIR);
+        let light_space = light_space_matrix(bounds_min, bounds_max, light_dir);
+
+        let light_uniform_buf = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Light Uniform Buffer"),
+            size: std::mem::size_of::<LightUniform>() as wgpu::BufferAddress,
+            usage: wgpu::BufferUsage::UNIFORM | wgpu::BufferUsage::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        let shadow_sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("Shadow Sampler"),
+            address_mode_u: wgpu::AddressMode::ClampToEdge,
+            address_mode_v: wgpu::AddressMode::ClampToEdge,
+            address_mode_w: wgpu::AddressMode::ClampToEdge,
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            mipmap_filter: wgpu::FilterMode::Nearest,
+            compare: Some(wgpu::CompareFunction::LessEqual),
+            ..Default::default()
+        });
+
+        let shadow_texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("Shadow Map"),
+            size: wgpu::Extent3d {
+                width: SHADOW_MAP_SIZE,
+                height: SHADOW_MAP_SIZE,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: wgpu::TextureFormat::Depth32Float,
+            usage: wgpu::TextureUsage::RENDER_ATTACHMENT | wgpu::TextureUsage::SAMPLED,
+        });
+        let shadow_view = shadow_texture.create_view(&wgpu::TextureViewDescriptor::default());
+
+        let light_bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("Light Bind Group Layout"),
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStage::VERTEX | wgpu::ShaderStage::FRAGMENT,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: wgpu::BufferSize::new(
+                            std::mem::size_of::<LightUniform>() as u64),
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStage::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        sample_type: wgpu::TextureSampleType::Depth,
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: wgpu::ShaderStage::FRAGMENT,
+                    ty: wgpu::BindingType::Sampler {
+                        comparison: true,
+                        filtering: true,
+                    },
+                    count: None,
+                },
+            ],
+        });
+
+        let light_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("Light Bind Group"),
+            layout: &light_bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: light_uniform_buf.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::TextureView(&shadow_view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: wgpu::BindingResource::Sampler(&shadow_sampler),
+                },
+            ],
+        });
+
+        let shadow_uniform_buf = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Shadow Pass Uniform Buffer"),
+            size: std::mem::size_of::<Mat4>() as wgpu::BufferAddress,
+            usage: wgpu::BufferUsage::UNIFORM | wgpu::BufferUsage::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        let shadow_bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("Shadow Pass Bind Group Layout"),
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStage::VERTEX,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: wgpu::BufferSize::new(
+                            std::mem::size_of::<Mat4>() as u64),
+                    },
+                    count: None,
+                },
+            ],
+        });
+
+        let shadow_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("Shadow Pass Bind Group"),
+            layout: &shadow_bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: shadow_uniform_buf.as_entire_binding(),
+                },
+            ],
+        });
+
         // Create pipeline layout
         let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
             label: None,
-            bind_group_layouts: &[&bind_group_layout],
+            bind_group_layouts: &[&bind_group_layout, &light_bind_group_layout],
+            push_constant_ranges: &[],
+        });
+
+        let shadow_pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Shadow Pipeline Layout"),
+            bind_group_layouts: &[&shadow_bind_group_layout],
             push_constant_ranges: &[],
         });
 
@@ -179,7 +546,7 @@ impl Model {
                 vertex: wgpu::VertexState {
                     module: &shader,
                     entry_point: "vs_main",
-                    buffers: &[vertex_buf_layout],
+                    buffers: &[vertex_buf_layout.clone()],
                 },
                 fragment: Some(wgpu::FragmentState {
                     module: &shader,
@@ -194,23 +561,125 @@ impl Model {
                     stencil: wgpu::StencilState::default(),
                     bias: wgpu::DepthBiasState::default(),
                 }),
+                multisample: wgpu::MultisampleState {
+                    count: msaa_sample_count,
+                    mask: !0,
+                    alpha_to_coverage_enabled: false,
+                },
+        });
+
+        let (depth_view, msaa_color_view) = Self::create_targets(device, swapchain_format, size, msaa_sample_count);
+
+        // Depth-only pass that renders the mesh from the light's point of
+        // view into `shadow_view`; no fragment target is bound.
+        let shadow_pipeline = device.create_render_pipeline(
+            &wgpu::RenderPipelineDescriptor {
+                label: Some("Shadow Pipeline"),
+                layout: Some(&shadow_pipeline_layout),
+                vertex: wgpu::VertexState {
+                    module: &shader,
+                    entry_point: "vs_shadow",
+                    buffers: &[vertex_buf_layout.clone()],
+                },
+                fragment: None,
+                primitive: wgpu::PrimitiveState::default(),
+                depth_stencil: Some(wgpu::DepthStencilState {
+                    format: wgpu::TextureFormat::Depth32Float,
+                    depth_write_enabled: true,
+                    depth_compare: wgpu::CompareFunction::LessEqual,
+                    stencil: wgpu::StencilState::default(),
+                    bias: wgpu::DepthBiasState {
+                        constant: 2,
+                        slope_scale: 2.0,
+                        clamp: 0.0,
+                    },
+                }),
                 multisample: wgpu::MultisampleState::default(),
         });
 
-        (Model {
+        Ok((Model {
             render_pipeline,
-            index_buf,
-            vertex_buf,
+            primitives: primitives_gpu,
             uniform_buf,
             bind_group,
-            index_count: indices.len() as u32 // index_count: tris.len() as u32 * 3,
-        }, vertices)
+
+            light_uniform_buf,
+            light_bind_group,
+            light_space,
+
+            shadow_uniform_buf,
+            shadow_bind_group,
+            shadow_pipeline,
+            shadow_view,
+
+            swapchain_format,
+            msaa_sample_count,
+            msaa_color_view,
+            depth_view,
+        }, vertices))
+    }
+
+    /// Creates the depth target (and, with MSAA on, the multisampled color
+    /// target) sized to `size`. Shared by `new()` and `resize()` so the two
+    /// can't drift out of sync on format/sample count.
+    fn create_targets(
+        device: &wgpu::Device,
+        swapchain_format: wgpu::TextureFormat,
+        size: (u32, u32),
+        msaa_sample_count: u32,
+    ) -> (wgpu::TextureView, Option<wgpu::TextureView>) {
+        let texture_size = wgpu::Extent3d {
+            width: size.0,
+            height: size.1,
+            depth_or_array_layers: 1,
+        };
+
+        let depth_texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("Depth Texture"),
+            size: texture_size,
+            mip_level_count: 1,
+            sample_count: msaa_sample_count,
+            dimension: wgpu::TextureDimension::D2,
+            format: wgpu::TextureFormat::Depth32Float,
+            usage: wgpu::TextureUsage::RENDER_ATTACHMENT,
+        });
+        let depth_view = depth_texture.create_view(&wgpu::TextureViewDescriptor::default());
+
+        // With MSAA the pipeline renders into this multisampled color
+        // target; the swapchain texture is only the resolve target each
+        // frame. With a single sample there's nothing to resolve, so the
+        // pipeline draws straight to the swapchain view as before.
+        let msaa_color_view = if msaa_sample_count > 1 {
+            let msaa_texture = device.create_texture(&wgpu::TextureDescriptor {
+                label: Some("MSAA Color Target"),
+                size: texture_size,
+                mip_level_count: 1,
+                sample_count: msaa_sample_count,
+                dimension: wgpu::TextureDimension::D2,
+                format: swapchain_format,
+                usage: wgpu::TextureUsage::RENDER_ATTACHMENT,
+            });
+            Some(msaa_texture.create_view(&wgpu::TextureViewDescriptor::default()))
+        } else {
+            None
+        };
+
+        (depth_view, msaa_color_view)
+    }
+
+    /// Recreates the depth and MSAA color targets at the new swapchain
+    /// size. Must be called whenever the window is resized: both targets
+    /// are sized once from whatever `size` was passed to `Model::new` and
+    /// don't track the swapchain on their own.
+    pub fn resize(&mut self, device: &wgpu::Device, size: (u32, u32)) {
+        let (depth_view, msaa_color_view) = Self::create_targets(device, self.swapchain_format, size, self.msaa_sample_count);
+        self.depth_view = depth_view;
+        self.msaa_color_view = msaa_color_view;
     }
 
     pub fn draw(&self, camera: &Camera,
                 queue: &wgpu::Queue,
                 frame: &wgpu::SwapChainTexture,
-                depth_view: &wgpu::TextureView,
                 encoder: &mut wgpu::CommandEncoder)
     {
         // Update the uniform buffer with our new matrix
@@ -222,31 +691,84 @@ impl Model {
             std::mem::size_of::<Mat4>() as wgpu::BufferAddress,
             bytemuck::cast_slice(model_mat.as_slice()));
 
+        let inv_view = glm::inverse(&view_mat);
+        let camera_pos = glm::vec3(inv_view[(0, 3)], inv_view[(1, 3)], inv_view[(2, 3)]);
+        let light_dir = glm::make_vec3(&DEFAULT_LIGHT_DIR);
+        let light_uniform = LightUniform {
+            light_space: self.light_space.into(),
+            light_dir: [light_dir.x, light_dir.y, light_dir.z, 0.0],
+            light_color: [DEFAULT_LIGHT_COLOR[0], DEFAULT_LIGHT_COLOR[1], DEFAULT_LIGHT_COLOR[2], 1.0],
+            camera_pos: [camera_pos.x, camera_pos.y, camera_pos.z, 1.0],
+        };
+        queue.write_buffer(&self.light_uniform_buf, 0, bytemuck::bytes_of(&light_uniform));
+
+        let shadow_mvp: Mat4 = self.light_space * model_mat;
+        queue.write_buffer(&self.shadow_uniform_buf, 0,
+            bytemuck::cast_slice(shadow_mvp.as_slice()));
+
+        // Shadow pass: render depth-only from the light's point of view.
+        {
+            let mut shadow_pass = encoder.begin_render_pass(
+                &wgpu::RenderPassDescriptor {
+                    label: Some("Shadow Pass"),
+                    color_attachments: &[],
+                    depth_stencil_attachment: Some(
+                        wgpu::RenderPassDepthStencilAttachment {
+                            view: &self.shadow_view,
+                            depth_ops: Some(wgpu::Operations {
+                                load: wgpu::LoadOp::Clear(1.0),
+                                store: true,
+                            }),
+                            stencil_ops: None,
+                        }),
+                });
+            shadow_pass.set_pipeline(&self.shadow_pipeline);
+            shadow_pass.set_bind_group(0, &self.shadow_bind_group, &[]);
+            for primitive in &self.primitives {
+                shadow_pass.set_index_buffer(primitive.index_buf.slice(..), wgpu::IndexFormat::Uint32);
+                shadow_pass.set_vertex_buffer(0, primitive.vertex_buf.slice(..));
+                shadow_pass.draw_indexed(0..primitive.index_count, 0, 0..1);
+            }
+        }
+
+        // With MSAA, render into the multisampled color target and resolve
+        // into the swapchain texture; with a single sample, draw straight
+        // to the swapchain view and skip the resolve.
+        let (color_view, resolve_target) = match &self.msaa_color_view {
+            Some(msaa_view) => (msaa_view, Some(&frame.view)),
+            None => (&frame.view, None),
+        };
+
         let mut rpass = encoder.begin_render_pass(
             &wgpu::RenderPassDescriptor {
                 label: None,
                 color_attachments: &[wgpu::RenderPassColorAttachment {
-                    view: &frame.view,
-                    resolve_target: None,
+                    view: color_view,
+                    resolve_target,
                     ops: wgpu::Operations {
-                        load: wgpu::LoadOp::Load,
+                        load: wgpu::LoadOp::Clear(wgpu::Color::BLACK),
                         store: true,
                     },
                 }],
                 depth_stencil_attachment: Some(
                     wgpu::RenderPassDepthStencilAttachment {
-                        view: &depth_view,
+                        view: &self.depth_view,
+                        // Reverse-Z (see `depth_compare: Greater` above): the
+                        // far plane is 0.0, so that's the clear value, not 1.0.
                         depth_ops: Some(wgpu::Operations {
-                            load: wgpu::LoadOp::Load,
+                            load: wgpu::LoadOp::Clear(0.0),
                             store: true,
                         }),
                         stencil_ops: None,
                     }),
             });
         rpass.set_pipeline(&self.render_pipeline);
-        rpass.set_index_buffer(self.index_buf.slice(..), wgpu::IndexFormat::Uint32);
-        rpass.set_vertex_buffer(0, self.vertex_buf.slice(..));
         rpass.set_bind_group(0, &self.bind_group, &[]);
-        rpass.draw_indexed(0..self.index_count, 0, 0..1);
+        rpass.set_bind_group(1, &self.light_bind_group, &[]);
+        for primitive in &self.primitives {
+            rpass.set_index_buffer(primitive.index_buf.slice(..), wgpu::IndexFormat::Uint32);
+            rpass.set_vertex_buffer(0, primitive.vertex_buf.slice(..));
+            rpass.draw_indexed(0..primitive.index_count, 0, 0..1);
+        }
     }
 }
@@ -21,6 +21,7 @@ use std::borrow::Cow;
 use wasm_bindgen::prelude::*;
 use log::Level;
 use log::info;
+use log::warn;
 
 #[derive(Copy, Clone, Debug, bytemuck::NoUninit)]
 #[repr(C)]
@@ -30,8 +31,57 @@ struct Vertex {
     normal: [f32; 3],
 }
 
+const WELD_GRID: f32 = 1.0e4;
+
+fn quantize(f: f32) -> i32 {
+    (f * WELD_GRID).round() as i32
+}
+
+/// Geometry-only vertex: once color moves to the material, primitives
+/// only need to carry position and normal.
+#[derive(Copy, Clone, Debug, bytemuck::NoUninit)]
+#[repr(C)]
+struct GeomVertex {
+    position: [f32; 3],
+    normal: [f32; 3],
+}
+
+/// Quantized key used to weld coincident vertices before indexing.
+///
+/// Positions and normals are snapped to a fixed grid so that two
+/// corners emitted by the triangulator for the "same" vertex hash
+/// identically even if they differ by float noise.
+#[derive(Copy, Clone, PartialEq, Eq, Hash)]
+struct GeomWeldKey([i32; 6]);
+
+fn geom_weld_key(v: &GeomVertex) -> GeomWeldKey {
+    GeomWeldKey([
+        quantize(v.position[0]), quantize(v.position[1]), quantize(v.position[2]),
+        quantize(v.normal[0]), quantize(v.normal[1]), quantize(v.normal[2]),
+    ])
+}
+
+/// Welds coincident vertices and returns the deduplicated vertex list
+/// plus the per-corner index buffer referencing it.
+fn weld_geom_vertices(corners: &[GeomVertex]) -> (Vec<GeomVertex>, Vec<u32>) {
+    let mut welded: Vec<GeomVertex> = Vec::new();
+    let mut remap: std::collections::HashMap<GeomWeldKey, u32> = std::collections::HashMap::new();
+    let mut indices: Vec<u32> = Vec::with_capacity(corners.len());
+
+    for v in corners {
+        let key = geom_weld_key(v);
+        let idx = *remap.entry(key).or_insert_with(|| {
+            welded.push(*v);
+            (welded.len() - 1) as u32
+        });
+        indices.push(idx);
+    }
+
+    (welded, indices)
+}
+
 /// Calculate bounding coordinates of a list of vertices, used for the clipping distance of the model
-fn bounding_coords(points: &[Vertex]) -> ([f32; 3], [f32; 3]) {
+fn bounding_coords(points: &[GeomVertex]) -> ([f32; 3], [f32; 3]) {
     let mut min = [f32::MAX, f32::MAX, f32::MAX];
     let mut max = [f32::MIN, f32::MIN, f32::MIN];
 
@@ -45,20 +95,340 @@ fn bounding_coords(points: &[Vertex]) -> ([f32; 3], [f32; 3]) {
     (min, max)
 }
 
+/// Quantized key used to group triangle corners into distinct STEP
+/// color/style assignments, one glTF material per key.
+#[derive(Copy, Clone, PartialEq, Eq, Hash)]
+struct ColorKey([i32; 3]);
+
+fn color_key(c: [f32; 3]) -> ColorKey {
+    ColorKey([quantize(c[0]), quantize(c[1]), quantize(c[2])])
+}
+
 fn align_to_multiple_of_four(n: &mut usize) {
     *n = (*n + 3) & !3;
 }
 
-fn to_padded_byte_vector<T: bytemuck::NoUninit>(data: &[T]) -> Vec<u8> {
-    let byte_slice: &[u8] = bytemuck::cast_slice(data);
-    let mut new_vec: Vec<u8> = byte_slice.to_owned();
+/// Disjoint-set over triangle indices, used to group the flat triangle
+/// soup into connected solids (see `step_to_gltf`'s use below).
+struct UnionFind {
+    parent: Vec<usize>,
+    size: Vec<usize>,
+}
+
+impl UnionFind {
+    fn new(n: usize) -> Self {
+        UnionFind { parent: (0..n).collect(), size: vec![1; n] }
+    }
+
+    fn find(&mut self, x: usize) -> usize {
+        if self.parent[x] != x {
+            self.parent[x] = self.find(self.parent[x]);
+        }
+        self.parent[x]
+    }
+
+    /// Unions `a` and `b`, returning `true` if doing so fused two clusters
+    /// that were *each already more than one triangle*, as opposed to
+    /// folding a single new triangle into a cluster that's still growing.
+    /// The former is the closest this union-find can get to flagging "two
+    /// distinct solids that happen to touch". Unions run in whatever order
+    /// the caller visits shared vertices, so two partial clusters of one
+    /// solid also set this flag: it marks a possible merge, not a certain one.
+    fn union(&mut self, a: usize, b: usize) -> bool {
+        let (ra, rb) = (self.find(a), self.find(b));
+        if ra == rb {
+            return false;
+        }
+        let fused_two_clusters = self.size[ra] > 1 && self.size[rb] > 1;
+        self.parent[ra] = rb;
+        self.size[rb] += self.size[ra];
+        fused_two_clusters
+    }
+}
 
+/// Splits a flat triangle soup into connected components (triangles that
+/// share a vertex position, transitively), returned as lists of triangle
+/// indices into `corners.chunks(3)`.
+///
+/// `triangulate()` flattens a STEP assembly's product-definition graph
+/// into one big vertex/triangle soup with no product or placement IDs
+/// attached, so connectivity is the only signal this function has left
+/// to recover "one distinct solid" from: disjoint geometry means disjoint
+/// solids, while separate parts that share a vertex position (parts that
+/// touch) end up in one component.
+fn connected_components(corners: &[Vertex]) -> Vec<Vec<usize>> {
+    let num_tris = corners.len() / 3;
+    let mut uf = UnionFind::new(num_tris);
+
+    let mut triangles_at_position: std::collections::HashMap<[i32; 3], Vec<usize>> = std::collections::HashMap::new();
+    for t in 0..num_tris {
+        for corner in &corners[t * 3..t * 3 + 3] {
+            let key = [quantize(corner.position[0]), quantize(corner.position[1]), quantize(corner.position[2])];
+            triangles_at_position.entry(key).or_default().push(t);
+        }
+    }
+    let mut cluster_fusions = 0;
+    for triangles in triangles_at_position.values() {
+        for pair in triangles.windows(2) {
+            if uf.union(pair[0], pair[1]) {
+                cluster_fusions += 1;
+            }
+        }
+    }
+    if cluster_fusions > 0 {
+        warn!(
+            "connected_components fused {} pair(s) of already-multi-triangle clusters at a shared vertex; \
+             if any of those were distinct STEP parts that merely touch (e.g. a bolt through a hole) rather \
+             than one solid, they were merged into a single mesh instead of kept as separate instances",
+            cluster_fusions
+        );
+    }
 
-    while new_vec.len() % 4 != 0 {
-        new_vec.push(0); // pad to multiple of four bytes
+    let mut components: std::collections::HashMap<usize, Vec<usize>> = std::collections::HashMap::new();
+    for t in 0..num_tris {
+        components.entry(uf.find(t)).or_default().push(t);
     }
+    components.into_values().collect()
+}
+
+/// Quantized, order-independent shape signature for a component that has
+/// already been recentered to its own local origin (see `step_to_gltf`).
+/// Two components with the same signature are the same part placed at a
+/// different spot, so they can share one `json::Mesh`.
+fn shape_signature(recentered_corners: &[Vertex]) -> Vec<[i32; 9]> {
+    let mut sig: Vec<[i32; 9]> = recentered_corners.iter()
+        .map(|v| [
+            quantize(v.position[0]), quantize(v.position[1]), quantize(v.position[2]),
+            quantize(v.normal[0]), quantize(v.normal[1]), quantize(v.normal[2]),
+            quantize(v.color[0]), quantize(v.color[1]), quantize(v.color[2]),
+        ])
+        .collect();
+    sig.sort_unstable();
+    sig
+}
+
+/// Cheap, rotation-invariant stand-in for `shape_signature`: the corner
+/// count and the quantized distance from the local origin to the farthest
+/// corner. Two components can share this while still differing in
+/// `shape_signature` if one is a rotated copy of the other -
+/// `translation_matrix` can't recover that rotation, so `step_to_gltf`
+/// warns instead of silently shipping a second full-size mesh for what may
+/// be the same part at a different orientation.
+fn rough_shape_signature(recentered_corners: &[Vertex]) -> (usize, i32) {
+    let max_dist = recentered_corners.iter()
+        .map(|v| quantize((v.position[0].powi(2) + v.position[1].powi(2) + v.position[2].powi(2)).sqrt()))
+        .max()
+        .unwrap_or(0);
+    (recentered_corners.len(), max_dist)
+}
+
+/// Translation-only placement matrix, in glTF's column-major `Node::matrix`
+/// layout.
+///
+/// A real `AXIS2_PLACEMENT_3D` can also rotate, but `triangulate()`
+/// already bakes every placement into world-space triangle coordinates,
+/// so the only transform `step_to_gltf` can recover between two
+/// occurrences of the same part is the offset between their centroids.
+fn translation_matrix(t: [f32; 3]) -> [f32; 16] {
+    [
+        1.0, 0.0, 0.0, 0.0,
+        0.0, 1.0, 0.0, 0.0,
+        0.0, 0.0, 1.0, 0.0,
+        t[0], t[1], t[2], 1.0,
+    ]
+}
+
+/// One placement of a mesh in the assembly's scene graph.
+///
+/// A bolt used forty times in a STEP assembly is one `mesh` referenced
+/// by forty of these, each carrying its own placement `transform` and
+/// nested under whatever sub-assembly placed it. `step_to_gltf` only has
+/// enough information today to build a flat list of top-level instances
+/// (see its doc comment); `children` exists so a future pass with real
+/// STEP product/shape-representation nesting can build a proper forest
+/// without changing this type.
+struct AssemblyInstance {
+    mesh: json::Index<json::Mesh>,
+    transform: Option<[f32; 16]>,
+    children: Vec<AssemblyInstance>,
+}
 
-    new_vec
+/// Recursively pushes an `AssemblyInstance` (and its children) as glTF
+/// nodes, writing the placement transform into `Node::matrix`.
+fn push_instance(root: &mut json::Root, instance: &AssemblyInstance) -> json::Index<json::Node> {
+    let children: Vec<json::Index<json::Node>> = instance
+        .children
+        .iter()
+        .map(|child| push_instance(root, child))
+        .collect();
+
+    root.push(json::Node {
+        mesh: Some(instance.mesh),
+        matrix: instance.transform,
+        children: if children.is_empty() { None } else { Some(children) },
+        ..Default::default()
+    })
+}
+
+/// Builds one glTF mesh from a flat list of per-corner vertices belonging
+/// to a single solid, grouping corners by their STEP presentation color
+/// (STYLED_ITEM / COLOUR_RGB) into one `Primitive` per material and
+/// welding coincident corners within each color group. All primitives
+/// share `buffer`, so multiple solids can still end up in one .glb chunk.
+fn build_mesh(
+    root: &mut json::Root,
+    bin: &mut Vec<u8>,
+    buffer: json::Index<json::Buffer>,
+    corners: &[Vertex],
+) -> json::Index<json::Mesh> {
+    let mut material_of_color: std::collections::HashMap<ColorKey, usize> = std::collections::HashMap::new();
+    let mut groups: Vec<([f32; 3], Vec<GeomVertex>)> = Vec::new();
+    for tri in corners.chunks(3) {
+        let color = tri[0].color;
+        let group = *material_of_color.entry(color_key(color)).or_insert_with(|| {
+            groups.push((color, Vec::new()));
+            groups.len() - 1
+        });
+        groups[group].1.extend(tri.iter().map(|v| GeomVertex {
+            position: v.position,
+            normal: v.normal,
+        }));
+    }
+
+    let materials: Vec<json::Index<json::Material>> = groups.iter()
+        .map(|(color, _)| root.push(json::Material {
+            alpha_cutoff: None,
+            alpha_mode: Valid(json::material::AlphaMode::Opaque),
+            double_sided: true,
+            pbr_metallic_roughness: json::material::PbrMetallicRoughness {
+                base_color_factor: json::material::PbrBaseColorFactor([color[0], color[1], color[2], 1.0]),
+                metallic_factor: json::material::StrengthFactor(0.1),
+                roughness_factor: json::material::StrengthFactor(0.8),
+                ..Default::default()
+            },
+            normal_texture: None,
+            occlusion_texture: None,
+            emissive_texture: None,
+            emissive_factor: Default::default(),
+            extensions: Default::default(),
+            extras: Default::default(),
+            name: None,
+        }))
+        .collect();
+
+    let mut primitives: Vec<json::mesh::Primitive> = Vec::with_capacity(groups.len());
+
+    for (group_idx, (_, group_corners)) in groups.iter().enumerate() {
+        let (welded, corner_indices) = weld_geom_vertices(group_corners);
+        let use_u16 = welded.len() <= u16::MAX as usize;
+        let (min, max) = bounding_coords(&welded);
+
+        let vertex_byte_offset = bin.len();
+        bin.extend_from_slice(bytemuck::cast_slice(&welded));
+
+        let index_byte_offset = bin.len();
+        if use_u16 {
+            let narrow_indices: Vec<u16> = corner_indices.iter().map(|&i| i as u16).collect();
+            bin.extend_from_slice(bytemuck::cast_slice(&narrow_indices));
+        } else {
+            bin.extend_from_slice(bytemuck::cast_slice(&corner_indices));
+        }
+        while bin.len() % 4 != 0 {
+            bin.push(0); // pad to multiple of four bytes before the next group
+        }
+
+        let vertex_buffer_view = root.push(json::buffer::View {
+            buffer,
+            byte_length: USize64::from(welded.len() * mem::size_of::<GeomVertex>()),
+            byte_offset: Some(USize64::from(vertex_byte_offset)),
+            byte_stride: Some(json::buffer::Stride(mem::size_of::<GeomVertex>())),
+            extensions: Default::default(),
+            extras: Default::default(),
+            name: None,
+            target: Some(Valid(json::buffer::Target::ArrayBuffer)),
+        });
+        let index_buffer_view = root.push(json::buffer::View {
+            buffer,
+            byte_length: USize64::from(bin.len() - index_byte_offset),
+            byte_offset: Some(USize64::from(index_byte_offset)),
+            byte_stride: None,
+            extensions: Default::default(),
+            extras: Default::default(),
+            name: None,
+            target: Some(Valid(json::buffer::Target::ElementArrayBuffer)),
+        });
+        let positions = root.push(json::Accessor {
+            buffer_view: Some(vertex_buffer_view),
+            byte_offset: Some(USize64(0)),
+            count: USize64::from(welded.len()),
+            component_type: Valid(json::accessor::GenericComponentType(
+                json::accessor::ComponentType::F32,
+            )),
+            extensions: Default::default(),
+            extras: Default::default(),
+            type_: Valid(json::accessor::Type::Vec3),
+            min: Some(json::Value::from(Vec::from(min))),
+            max: Some(json::Value::from(Vec::from(max))),
+            name: None,
+            normalized: false,
+            sparse: None,
+        });
+        let normals = root.push(json::Accessor {
+            buffer_view: Some(vertex_buffer_view),
+            byte_offset: Some(USize64::from(3 * mem::size_of::<f32>())),
+            count: USize64::from(welded.len()),
+            component_type: Valid(json::accessor::GenericComponentType(
+                json::accessor::ComponentType::F32,
+            )),
+            extensions: Default::default(),
+            extras: Default::default(),
+            type_: Valid(json::accessor::Type::Vec3),
+            min: None,
+            max: None,
+            name: None,
+            normalized: false,
+            sparse: None,
+        });
+        let indices = root.push(json::Accessor {
+            buffer_view: Some(index_buffer_view),
+            byte_offset: Some(USize64(0)),
+            count: USize64::from(corner_indices.len()),
+            component_type: Valid(json::accessor::GenericComponentType(
+                if use_u16 { json::accessor::ComponentType::U16 } else { json::accessor::ComponentType::U32 },
+            )),
+            extensions: Default::default(),
+            extras: Default::default(),
+            type_: Valid(json::accessor::Type::Scalar),
+            min: None,
+            max: None,
+            name: None,
+            normalized: false,
+            sparse: None,
+        });
+
+        primitives.push(json::mesh::Primitive {
+            attributes: {
+                let mut map = std::collections::BTreeMap::new();
+                map.insert(Valid(json::mesh::Semantic::Positions), positions);
+                map.insert(Valid(json::mesh::Semantic::Normals), normals);
+                map
+            },
+            extensions: Default::default(),
+            extras: Default::default(),
+            indices: Some(indices),
+            material: Some(materials[group_idx]),
+            mode: Valid(json::mesh::Mode::Triangles),
+            targets: None,
+        });
+    }
+
+    root.push(json::Mesh {
+        extensions: Default::default(),
+        extras: Default::default(),
+        name: None,
+        primitives,
+        weights: None,
+    })
 }
 
 #[wasm_bindgen]
@@ -183,111 +553,103 @@ pub fn step_to_gltf(data: String) -> Vec<u8> {
 
     info!("Mapped triangles");
 
-    let (min, max) = bounding_coords(&triangle_vertices);
+    // `triangulate()` hands back one flat triangle soup with no STEP
+    // product-definition or AXIS2_PLACEMENT_3D identity attached, so a
+    // disjoint-geometry pass is the only way left to recover "one solid
+    // per instance"; separate STEP parts that share a vertex position are
+    // merged into one component (see `connected_components`).
+    // Each component becomes its own `json::Mesh`/`json::Node`, and
+    // components with an identical recentered shape (the repeated-bolt
+    // case) share one `json::Mesh`, referenced by one `json::Node` per
+    // occurrence with a translation to its centroid. Colors still split
+    // each mesh into one `Primitive` per material (see `build_mesh`).
+    let components = connected_components(&triangle_vertices);
+
+    info!("Split {} triangles into {} connected solids", triangle_vertices.len() / 3, components.len());
 
     let mut root = json::Root::default();
 
-    let buffer_length = triangle_vertices.len() * mem::size_of::<Vertex>();
+    // All solids still share one binary blob so the .glb stays one chunk.
     let buffer = root.push(json::Buffer {
-        byte_length: USize64::from(buffer_length),
+        byte_length: USize64(0), // patched once the blob length is known
         extensions: Default::default(),
         extras: Default::default(),
         name: None,
         uri: None,
     });
-    let buffer_view = root.push(json::buffer::View {
-        buffer,
-        byte_length: USize64::from(buffer_length),
-        byte_offset: None,
-        byte_stride: Some(json::buffer::Stride(mem::size_of::<Vertex>())),
-        extensions: Default::default(),
-        extras: Default::default(),
-        name: None,
-        target: Some(Valid(json::buffer::Target::ArrayBuffer)),
-    });
-    let positions = root.push(json::Accessor {
-        buffer_view: Some(buffer_view),
-        byte_offset: Some(USize64(0)),
-        count: USize64::from(triangle_vertices.len()),
-        component_type: Valid(json::accessor::GenericComponentType(
-            json::accessor::ComponentType::F32,
-        )),
-        extensions: Default::default(),
-        extras: Default::default(),
-        type_: Valid(json::accessor::Type::Vec3),
-        min: Some(json::Value::from(Vec::from(min))),
-        max: Some(json::Value::from(Vec::from(max))),
-        name: None,
-        normalized: false,
-        sparse: None,
-    });
-    let colors = root.push(json::Accessor {
-        buffer_view: Some(buffer_view),
-        byte_offset: Some(USize64::from(3 * mem::size_of::<f32>())),
-        count: USize64::from(triangle_vertices.len()),
-        component_type: Valid(json::accessor::GenericComponentType(
-            json::accessor::ComponentType::F32,
-        )),
-        extensions: Default::default(),
-        extras: Default::default(),
-        type_: Valid(json::accessor::Type::Vec3),
-        min: None,
-        max: None,
-        name: None,
-        normalized: false,
-        sparse: None,
-    });
-    let normals = root.push(json::Accessor {
-        buffer_view: Some(buffer_view),
-        byte_offset: Some(USize64::from(6 * mem::size_of::<f32>())),
-        count: USize64::from(triangle_vertices.len()),
-        component_type: Valid(json::accessor::GenericComponentType(
-            json::accessor::ComponentType::F32,
-        )),
-        extensions: Default::default(),
-        extras: Default::default(),
-        type_: Valid(json::accessor::Type::Vec3),
-        min: None,
-        max: None,
-        name: None,
-        normalized: false,
-        sparse: None,
-    });
+    let mut bin: Vec<u8> = Vec::new();
 
-    let primitive = json::mesh::Primitive {
-        attributes: {
-            let mut map = std::collections::BTreeMap::new();
-            map.insert(Valid(json::mesh::Semantic::Positions), positions);
-            map.insert(Valid(json::mesh::Semantic::Colors(0)), colors);
-            map.insert(Valid(json::mesh::Semantic::Normals), normals);
-            map
-        },
-        extensions: Default::default(),
-        extras: Default::default(),
-        indices: None,
-        material: None,
-        mode: Valid(json::mesh::Mode::Triangles),
-        targets: None,
-    };
+    let mut mesh_of_shape: std::collections::HashMap<Vec<[i32; 9]>, json::Index<json::Mesh>> = std::collections::HashMap::new();
+    let mut shapes_of_rough_signature: std::collections::HashMap<(usize, i32), Vec<Vec<[i32; 9]>>> = std::collections::HashMap::new();
+    let mut instances: Vec<AssemblyInstance> = Vec::with_capacity(components.len());
 
-    let mesh = root.push(json::Mesh {
-        extensions: Default::default(),
-        extras: Default::default(),
-        name: None,
-        primitives: vec![primitive],
-        weights: None,
-    });
+    for triangle_indices in &components {
+        let corners: Vec<Vertex> = triangle_indices.iter()
+            .flat_map(|&t| triangle_vertices[t * 3..t * 3 + 3].iter().cloned())
+            .collect();
 
-    let node = root.push(json::Node {
-        mesh: Some(mesh),
-        ..Default::default()
-    });
+        let mut centroid = [0.0f32; 3];
+        for v in &corners {
+            for i in 0..3 {
+                centroid[i] += v.position[i];
+            }
+        }
+        for c in centroid.iter_mut() {
+            *c /= corners.len() as f32;
+        }
+
+        let recentered: Vec<Vertex> = corners.iter()
+            .map(|v| Vertex {
+                position: [v.position[0] - centroid[0], v.position[1] - centroid[1], v.position[2] - centroid[2]],
+                color: v.color,
+                normal: v.normal,
+            })
+            .collect();
+
+        let signature = shape_signature(&recentered);
+
+        let seen_with_same_rough_shape = shapes_of_rough_signature
+            .entry(rough_shape_signature(&recentered))
+            .or_default();
+        if !seen_with_same_rough_shape.is_empty() && !seen_with_same_rough_shape.contains(&signature) {
+            warn!(
+                "component has the same corner count and bounding radius as an earlier instance but a \
+                 different shape - likely the same part placed at a different rotation, which this pass \
+                 can't dedupe or place correctly (it will get its own full-size mesh at a translation-only \
+                 placement instead of sharing the existing mesh)"
+            );
+        }
+        seen_with_same_rough_shape.push(signature.clone());
+
+        let mesh = *mesh_of_shape.entry(signature).or_insert_with(|| build_mesh(&mut root, &mut bin, buffer, &recentered));
+
+        instances.push(AssemblyInstance {
+            mesh,
+            transform: Some(translation_matrix(centroid)),
+            children: Vec::new(),
+        });
+    }
+
+    while bin.len() % 4 != 0 {
+        bin.push(0);
+    }
+    root.buffers[buffer.value()].byte_length = USize64::from(bin.len());
+
+    info!("Built {} meshes for {} instances", mesh_of_shape.len(), instances.len());
+
+    // The flattened triangulator output carries no sub-assembly grouping
+    // either, so every instance sits directly under the scene rather than
+    // nested under a synthetic parent node that would misrepresent a
+    // hierarchy this data doesn't have.
+    let nodes: Vec<json::Index<json::Node>> = instances.iter()
+        .map(|instance| push_instance(&mut root, instance))
+        .collect();
 
     root.push(json::Scene {
         extensions: Default::default(),
         extras: Default::default(),
         name: None,
-        nodes: vec![node],
+        nodes,
     });
 
     info!("Gltf structs mapped");
@@ -296,17 +658,16 @@ pub fn step_to_gltf(data: String) -> Vec<u8> {
     let mut json_offset = json_string.len();
     align_to_multiple_of_four(&mut json_offset);
 
- 
     let glb = gltf::binary::Glb {
         header: gltf::binary::Header {
             magic: *b"glTF",
             version: 2,
             // N.B., the size of binary glTF file is limited to range of `u32`.
-            length: (json_offset + buffer_length)
+            length: (json_offset + bin.len())
                 .try_into()
                 .expect("file size exceeds binary glTF limit"),
         },
-        bin: Some(Cow::Owned(to_padded_byte_vector(&triangle_vertices))),
+        bin: Some(Cow::Owned(bin)),
         json: Cow::Owned(json_string.into_bytes()),
     };
 